@@ -58,12 +58,18 @@ fn setup(
         //     transform: Transform::from_xyz(0.0, -100.0, 0.0),
         //     ..default()
         // },
-        CharacterControllerBundle::new(Collider::capsule(12.5, 20.0)).with_movement(
-            1250.0,
-            0.92,
-            400.0,
-            (30.0 as Scalar).to_radians(),
-        ),
+        CharacterControllerBundle::new(Collider::capsule(12.5, 20.0))
+            .with_movement(MovementConfig {
+                acceleration: 1250.0,
+                damping: 0.92,
+                jump_impulse: 400.0,
+                max_slope_angle: (30.0 as Scalar).to_radians(),
+                air_jumps: 1,
+                air_acceleration: 625.0,
+                air_damping: 0.97,
+                max_speed: 600.0,
+            })
+            .with_dynamic_body_pushing(true, 1.0),
         Friction::ZERO.with_combine_rule(CoefficientCombine::Min),
         Restitution::ZERO.with_combine_rule(CoefficientCombine::Min),
         ColliderDensity(2.0),
@@ -5,17 +5,29 @@ pub struct CharacterControllerPlugin;
 
 impl Plugin for CharacterControllerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<MovementAction>().add_systems(
-            Update,
-            (
-                keyboard_input,
-                gamepad_input,
-                update_grounded,
-                movement,
-                apply_movement_damping,
-            )
-                .chain(),
-        );
+        app.add_event::<MovementAction>()
+            // Input is gathered every frame so edge-triggered presses (e.g.
+            // `just_pressed`) are never missed between physics steps.
+            .add_systems(Update, (keyboard_input, gamepad_input).chain())
+            // The rest of the controller runs inside avian's physics schedule
+            // and integrates against `Time<Physics>` (see `movement` and
+            // `apply_slope_slide`), draining the `MovementAction` queue at
+            // the fixed physics timestep so acceleration, jumps and damping
+            // no longer vary with the display frame rate.
+            .add_systems(
+                PhysicsSchedule,
+                (
+                    update_grounded,
+                    movement,
+                    apply_step_offset,
+                    apply_impulse_to_dynamic_bodies,
+                    apply_slope_slide,
+                    snap_to_ground,
+                    apply_movement_damping,
+                )
+                    .chain()
+                    .in_set(PhysicsStepSet::First),
+            );
     }
 }
 
@@ -31,13 +43,38 @@ pub enum MovementAction {
 pub struct CharacterController {
     wall_jumps: u32,
     last_wall: Option<Grounded>,
+    /// Whether the character was grounded or sliding as of last frame's
+    /// [`update_grounded`], used by [`snap_to_ground`] to tell a fresh
+    /// walk-off-a-ledge from having been airborne for a while.
+    was_grounded: bool,
+    /// Set on jump and cleared once [`update_grounded`] detects a natural
+    /// landing, so [`snap_to_ground`] never yanks the character back down
+    /// mid-jump.
+    just_jumped: bool,
+    /// Remaining mid-air jumps, replenished to [`AirJumps`] whenever
+    /// `update_grounded` finds the character resting on walkable ground
+    /// (within its `RESTING_CONTACT_DISTANCE`).
+    air_jumps_remaining: u32,
+    /// Whether [`apply_impulse_to_dynamic_bodies`] should push dynamic
+    /// rigid bodies the character collides with.
+    apply_impulse_to_dynamic_bodies: bool,
+    /// Scales the impulse applied to dynamic bodies the character pushes
+    /// into, so designers can tune how forceful shoving feels.
+    push_force: Scalar,
 }
 
 /// A marker component indicating that an entity is on the ground.
+///
+/// `Ground` and `Sliding` carry the hit surface's normal so that downstream
+/// systems (such as slope sliding) don't need to re-query it.
+/// `Sliding` means the character is touching ground steeper than its
+/// `MaxSlopeAngle`: no jump of any kind (ground, wall or air) is allowed from
+/// it, and movement uses the air acceleration and damping.
 #[derive(Component, PartialEq, Clone)]
 pub enum Grounded {
     None,
-    Ground,
+    Ground(Vector),
+    Sliding(Vector),
     LeftWall,
     RightWall,
 }
@@ -59,9 +96,40 @@ pub struct JumpImpulse(Scalar);
 #[derive(Component)]
 pub struct MaxSlopeAngle(Scalar);
 
+/// The budget of plain mid-air jumps a character gets after leaving the
+/// ground, on top of (and independent from) wall jumps.
+#[derive(Component)]
+pub struct AirJumps(u32);
+
+/// The acceleration used for character movement while airborne, separate
+/// from [`MovementAcceleration`] so air control can feel different from
+/// ground running.
+#[derive(Component)]
+pub struct AirAcceleration(Scalar);
+
+/// The damping factor used for slowing down movement while airborne,
+/// separate from [`MovementDampingFactor`].
+#[derive(Component)]
+pub struct AirDamping(Scalar);
+
+/// The maximum horizontal speed a character can reach through acceleration.
+#[derive(Component)]
+pub struct MaxSpeed(Scalar);
+
 #[derive(Component)]
 pub struct ShapeCastShape(Collider);
 
+/// The maximum distance the character will snap down onto the ground below
+/// it, so walking down a descending ramp or stairs doesn't launch it into a
+/// ballistic arc every frame.
+#[derive(Component)]
+pub struct SnapToGround(Scalar);
+
+/// The height of the tallest obstacle the character can walk over without
+/// jumping, analogous to a global step height (e.g. curbs, stair steps).
+#[derive(Component)]
+pub struct StepOffset(Scalar);
+
 /// A bundle that contains the components needed for a basic
 /// kinematic character controller.
 #[derive(Bundle)]
@@ -72,6 +140,38 @@ pub struct CharacterControllerBundle {
     caster_shape: ShapeCastShape,
     locked_axes: LockedAxes,
     movement: MovementBundle,
+    snap_to_ground: SnapToGround,
+    step_offset: StepOffset,
+}
+
+/// Tunables for [`MovementBundle`], grouped into one struct (rather than a
+/// long positional argument list) so call sites are self-documenting and
+/// can't silently swap two same-typed parameters.
+#[derive(Clone, Copy)]
+pub struct MovementConfig {
+    pub acceleration: Scalar,
+    pub damping: Scalar,
+    pub jump_impulse: Scalar,
+    pub max_slope_angle: Scalar,
+    pub air_jumps: u32,
+    pub air_acceleration: Scalar,
+    pub air_damping: Scalar,
+    pub max_speed: Scalar,
+}
+
+impl Default for MovementConfig {
+    fn default() -> Self {
+        Self {
+            acceleration: 30.0,
+            damping: 0.9,
+            jump_impulse: 7.0,
+            max_slope_angle: PI * 0.45,
+            air_jumps: 0,
+            air_acceleration: 15.0,
+            air_damping: 0.95,
+            max_speed: 500.0,
+        }
+    }
 }
 
 /// A bundle that contains components for character movement.
@@ -82,28 +182,31 @@ pub struct MovementBundle {
     damping: MovementDampingFactor,
     jump_impulse: JumpImpulse,
     max_slope_angle: MaxSlopeAngle,
+    air_jumps: AirJumps,
+    air_acceleration: AirAcceleration,
+    air_damping: AirDamping,
+    max_speed: MaxSpeed,
 }
 
 impl MovementBundle {
-    pub const fn new(
-        acceleration: Scalar,
-        damping: Scalar,
-        jump_impulse: Scalar,
-        max_slope_angle: Scalar,
-    ) -> Self {
+    pub const fn new(config: MovementConfig) -> Self {
         Self {
             grounded: Grounded::None,
-            acceleration: MovementAcceleration(acceleration),
-            damping: MovementDampingFactor(damping),
-            jump_impulse: JumpImpulse(jump_impulse),
-            max_slope_angle: MaxSlopeAngle(max_slope_angle),
+            acceleration: MovementAcceleration(config.acceleration),
+            damping: MovementDampingFactor(config.damping),
+            jump_impulse: JumpImpulse(config.jump_impulse),
+            max_slope_angle: MaxSlopeAngle(config.max_slope_angle),
+            air_jumps: AirJumps(config.air_jumps),
+            air_acceleration: AirAcceleration(config.air_acceleration),
+            air_damping: AirDamping(config.air_damping),
+            max_speed: MaxSpeed(config.max_speed),
         }
     }
 }
 
 impl Default for MovementBundle {
     fn default() -> Self {
-        Self::new(30.0, 0.9, 7.0, PI * 0.45)
+        Self::new(MovementConfig::default())
     }
 }
 
@@ -116,23 +219,40 @@ impl CharacterControllerBundle {
             character_controller: CharacterController {
                 wall_jumps: 0,
                 last_wall: None,
+                was_grounded: false,
+                just_jumped: false,
+                air_jumps_remaining: 0,
+                apply_impulse_to_dynamic_bodies: false,
+                push_force: 1.0,
             },
             rigid_body: RigidBody::Dynamic,
             collider,
             caster_shape: ShapeCastShape(caster_shape),
             locked_axes: LockedAxes::ROTATION_LOCKED,
             movement: MovementBundle::default(),
+            snap_to_ground: SnapToGround(10.0),
+            step_offset: StepOffset(15.0),
         }
     }
 
-    pub fn with_movement(
-        mut self,
-        acceleration: Scalar,
-        damping: Scalar,
-        jump_impulse: Scalar,
-        max_slope_angle: Scalar,
-    ) -> Self {
-        self.movement = MovementBundle::new(acceleration, damping, jump_impulse, max_slope_angle);
+    pub fn with_movement(mut self, config: MovementConfig) -> Self {
+        self.movement = MovementBundle::new(config);
+        self
+    }
+
+    pub fn with_snap_to_ground(mut self, max_snap_distance: Scalar) -> Self {
+        self.snap_to_ground = SnapToGround(max_snap_distance);
+        self
+    }
+
+    pub fn with_step_offset(mut self, step_height: Scalar) -> Self {
+        self.step_offset = StepOffset(step_height);
+        self
+    }
+
+    pub fn with_dynamic_body_pushing(mut self, enabled: bool, push_force: Scalar) -> Self {
+        self.character_controller.apply_impulse_to_dynamic_bodies = enabled;
+        self.character_controller.push_force = push_force;
         self
     }
 }
@@ -187,19 +307,34 @@ fn gamepad_input(
 
 /// Updates the [`Grounded`] status for character controllers.
 fn update_grounded(
-    mut commands: Commands,
     mut query: Query<
-        (Entity, &ShapeCastShape, &Position, &mut Grounded),
+        (
+            Entity,
+            &ShapeCastShape,
+            &Position,
+            &mut Grounded,
+            &MaxSlopeAngle,
+            &AirJumps,
+            &mut CharacterController,
+        ),
         With<CharacterController>,
     >,
     spatial_query: SpatialQuery,
 ) {
     // Create shape caster as a slightly smaller version of collider
 
-    for (entity, caster_shape, position, mut grounded) in &mut query {
+    // How close the downward cast hit has to be for the character to count as
+    // actually resting on it, as opposed to merely passing within the cast's
+    // much longer 20.0 lookahead distance (e.g. while still rising after a
+    // jump). Only a resting contact should refill the air-jump budget.
+    const RESTING_CONTACT_DISTANCE: Scalar = 2.0;
+
+    for (entity, caster_shape, position, mut grounded, max_slope_angle, air_jumps, mut controller) in
+        &mut query
+    {
         let filter = SpatialQueryFilter::default().with_excluded_entities([entity]);
 
-        if let Some(_hit) = spatial_query.cast_shape(
+        if let Some(hit) = spatial_query.cast_shape(
             &caster_shape.0, // Shape
             position.0,      // Origin
             0.0,             // Shape rotation
@@ -208,8 +343,18 @@ fn update_grounded(
             true,            // Should initial penetration at the origin be ignored
             filter.clone(),  // Query filter
         ) {
-            println!("Ground detected!");
-            *grounded = Grounded::Ground;
+            let slope_angle = hit.normal1.angle_between(Vector::Y).abs();
+            if slope_angle <= max_slope_angle.0 {
+                *grounded = Grounded::Ground(hit.normal1);
+                if hit.time_of_impact <= RESTING_CONTACT_DISTANCE {
+                    controller.air_jumps_remaining = air_jumps.0;
+                }
+            } else {
+                *grounded = Grounded::Sliding(hit.normal1);
+            }
+            if hit.time_of_impact <= RESTING_CONTACT_DISTANCE {
+                controller.just_jumped = false;
+            }
             continue;
         }
 
@@ -222,7 +367,6 @@ fn update_grounded(
             true,            // Should initial penetration at the origin be ignored
             filter.clone(),  // Query filter
         ) {
-            println!("right wall detected!");
             *grounded = Grounded::RightWall;
             continue;
         }
@@ -236,22 +380,27 @@ fn update_grounded(
             true,            // Should initial penetration at the origin be ignored
             filter,          // Query filter
         ) {
-            println!("left wall detected!");
             *grounded = Grounded::LeftWall;
             continue;
         }
 
-        println!("none detected!");
         *grounded = Grounded::None;
     }
 }
 
 /// Responds to [`MovementAction`] events and moves character controllers accordingly.
+///
+/// Reads `Time<Physics>` rather than the generic `Time` clock so acceleration
+/// and jump strength are pinned to avian's physics timestep - not whatever
+/// the display frame rate happens to be - even though this system runs in
+/// `PhysicsSchedule`.
 fn movement(
-    time: Res<Time>,
+    time: Res<Time<Physics>>,
     mut movement_event_reader: EventReader<MovementAction>,
     mut controllers: Query<(
         &MovementAcceleration,
+        &AirAcceleration,
+        &MaxSpeed,
         &JumpImpulse,
         &mut LinearVelocity,
         &Grounded,
@@ -261,18 +410,33 @@ fn movement(
     let delta_time = time.delta_seconds_f64().adjust_precision();
 
     for event in movement_event_reader.read() {
-        for (movement_acceleration, jump_impulse, mut linear_velocity, grounded, mut controller) in
-            &mut controllers
+        for (
+            movement_acceleration,
+            air_acceleration,
+            max_speed,
+            jump_impulse,
+            mut linear_velocity,
+            grounded,
+            mut controller,
+        ) in &mut controllers
         {
             match event {
                 MovementAction::Move(direction) => {
-                    linear_velocity.x += *direction * movement_acceleration.0 * delta_time;
+                    let acceleration = if matches!(*grounded, Grounded::Ground(_)) {
+                        movement_acceleration.0
+                    } else {
+                        air_acceleration.0
+                    };
+
+                    linear_velocity.x += *direction * acceleration * delta_time;
+                    linear_velocity.x = linear_velocity.x.clamp(-max_speed.0, max_speed.0);
                 }
                 MovementAction::Jump => match *grounded {
-                    Grounded::Ground => {
+                    Grounded::Ground(_) => {
                         linear_velocity.y = jump_impulse.0;
                         controller.wall_jumps = 0;
                         controller.last_wall = None;
+                        controller.just_jumped = true;
                     }
                     Grounded::LeftWall | Grounded::RightWall => {
                         if controller.wall_jumps == 0
@@ -286,19 +450,266 @@ fn movement(
                             };
                             controller.wall_jumps += 1;
                             controller.last_wall = Some(grounded.clone());
+                            controller.just_jumped = true;
                         }
                     }
-                    Grounded::None => {}
+                    Grounded::None => {
+                        if controller.air_jumps_remaining > 0 {
+                            linear_velocity.y = jump_impulse.0;
+                            controller.air_jumps_remaining -= 1;
+                            controller.just_jumped = true;
+                        }
+                    }
+                    Grounded::Sliding(_) => {}
                 },
             }
         }
     }
 }
 
+/// Lets characters walk up small ledges and stairs instead of stalling
+/// against them, by nudging `Position.y` up when the blocking obstacle in
+/// the movement direction is short enough to clear via [`StepOffset`].
+fn apply_step_offset(
+    mut query: Query<(
+        Entity,
+        &ShapeCastShape,
+        &Collider,
+        &StepOffset,
+        &mut Position,
+        &LinearVelocity,
+    )>,
+    spatial_query: SpatialQuery,
+) {
+    const FORWARD_PROBE_DISTANCE: Scalar = 2.0;
+    const CLEARANCE_MARGIN: Scalar = 1.0;
+
+    for (entity, caster_shape, collider, step_offset, mut position, linear_velocity) in &mut query
+    {
+        if linear_velocity.x == 0.0 {
+            continue;
+        }
+
+        let direction_sign: Scalar = if linear_velocity.x > 0.0 { 1.0 } else { -1.0 };
+        let direction = if direction_sign > 0.0 {
+            Dir2::X
+        } else {
+            Dir2::NEG_X
+        };
+        let filter = SpatialQueryFilter::default().with_excluded_entities([entity]);
+
+        // Is something blocking the character at its current height?
+        if spatial_query
+            .cast_shape(
+                &caster_shape.0,
+                position.0,
+                0.0,
+                direction,
+                FORWARD_PROBE_DISTANCE,
+                true,
+                filter.clone(),
+            )
+            .is_none()
+        {
+            continue;
+        }
+
+        let aabb = collider.aabb(Vector::ZERO, 0.0);
+        let half_height = (aabb.max.y - aabb.min.y) / 2.0;
+        let half_width = (aabb.max.x - aabb.min.x) / 2.0;
+        let foot_y = position.y - half_height;
+
+        // Probe straight down from just above the step height, at the spot
+        // the character would occupy if it climbed onto the obstacle, to
+        // find the obstacle's actual top edge and confirm there's room for
+        // the collider up there.
+        let probe_origin = Vector::new(
+            position.x + direction_sign * half_width,
+            position.y + step_offset.0 + CLEARANCE_MARGIN,
+        );
+
+        let Some(down_hit) = spatial_query.cast_shape(
+            &caster_shape.0,
+            probe_origin,
+            0.0,
+            Dir2::NEG_Y,
+            step_offset.0 + CLEARANCE_MARGIN,
+            true,
+            filter,
+        ) else {
+            // No surface within reach above - too tall, or a gap rather than a step.
+            continue;
+        };
+
+        // The cast moves the whole shape, so the hit gives the height of its
+        // centre; the obstacle's top is half a body below that.
+        let obstacle_top = probe_origin.y - down_hit.time_of_impact - half_height;
+        if obstacle_top > foot_y + step_offset.0 {
+            // Too tall to step over - let the character stall against it.
+            continue;
+        }
+
+        position.y = obstacle_top + half_height;
+    }
+}
+
+/// Pushes `RigidBody::Dynamic` entities the character collides with, so
+/// movable props react to the character's motion instead of just blocking
+/// it via the solver.
+fn apply_impulse_to_dynamic_bodies(
+    collisions: Res<Collisions>,
+    controllers: Query<(&CharacterController, &LinearVelocity, &Mass)>,
+    mut dynamic_bodies: Query<(&RigidBody, &mut LinearVelocity, &Mass), Without<CharacterController>>,
+) {
+    for contacts in collisions.iter() {
+        let (character_entity, body_entity) = if controllers.contains(contacts.entity1) {
+            (contacts.entity1, contacts.entity2)
+        } else if controllers.contains(contacts.entity2) {
+            (contacts.entity2, contacts.entity1)
+        } else {
+            continue;
+        };
+
+        let Ok((controller, character_velocity, character_mass)) =
+            controllers.get(character_entity)
+        else {
+            continue;
+        };
+
+        if !controller.apply_impulse_to_dynamic_bodies {
+            continue;
+        }
+
+        let Ok((body_rigid_body, mut body_velocity, body_mass)) =
+            dynamic_bodies.get_mut(body_entity)
+        else {
+            continue;
+        };
+
+        if *body_rigid_body != RigidBody::Dynamic {
+            continue;
+        }
+
+        for manifold in &contacts.manifolds {
+            // `normal1` points away from entity1, towards entity2 - flip it
+            // so it always points from the character towards the pushed body.
+            let normal = if character_entity == contacts.entity1 {
+                manifold.normal1
+            } else {
+                -manifold.normal1
+            };
+
+            let relative_velocity = character_velocity.0 - body_velocity.0;
+            let closing_speed = relative_velocity.dot(normal);
+            if closing_speed <= 0.0 {
+                // The character isn't moving into the body.
+                continue;
+            }
+
+            let impulse = normal * closing_speed * character_mass.0 * controller.push_force
+                / (character_mass.0 + body_mass.0);
+
+            // Push the body away from the character, along `normal`.
+            body_velocity.0 += impulse / body_mass.0;
+        }
+    }
+}
+
+/// Accelerates characters down the slope they're standing on when that slope
+/// is steeper than their [`MaxSlopeAngle`], so they can't rest on cliff faces.
+fn apply_slope_slide(
+    time: Res<Time<Physics>>,
+    mut query: Query<(&Grounded, &MovementAcceleration, &mut LinearVelocity)>,
+) {
+    let delta_time = time.delta_seconds_f64().adjust_precision();
+
+    for (grounded, movement_acceleration, mut linear_velocity) in &mut query {
+        if let Grounded::Sliding(normal) = *grounded {
+            // Rotate the normal 90 degrees to get a tangent along the slope,
+            // then flip it so it always points downhill.
+            let mut tangent = Vector::new(-normal.y, normal.x);
+            if tangent.y > 0.0 {
+                tangent = -tangent;
+            }
+
+            linear_velocity.x += tangent.x * movement_acceleration.0 * delta_time;
+        }
+    }
+}
+
+/// Keeps grounded characters glued to descending slopes and stairs instead of
+/// launching off them on every small gap, by snapping `Position.y` down onto
+/// the surface found within `SnapToGround`'s distance.
+fn snap_to_ground(
+    mut query: Query<(
+        Entity,
+        &ShapeCastShape,
+        &SnapToGround,
+        &MaxSlopeAngle,
+        &mut Position,
+        &mut LinearVelocity,
+        &mut Grounded,
+        &mut CharacterController,
+    )>,
+    spatial_query: SpatialQuery,
+) {
+    for (
+        entity,
+        caster_shape,
+        snap_distance,
+        max_slope_angle,
+        mut position,
+        mut linear_velocity,
+        mut grounded,
+        mut controller,
+    ) in &mut query
+    {
+        let was_grounded = controller.was_grounded;
+        controller.was_grounded = matches!(*grounded, Grounded::Ground(_) | Grounded::Sliding(_));
+
+        let moving_downward_or_level = linear_velocity.y <= 0.0;
+        if !was_grounded
+            || !moving_downward_or_level
+            || controller.just_jumped
+            || controller.was_grounded
+        {
+            continue;
+        }
+
+        let filter = SpatialQueryFilter::default().with_excluded_entities([entity]);
+
+        if let Some(hit) = spatial_query.cast_shape(
+            &caster_shape.0,
+            position.0,
+            0.0,
+            Dir2::NEG_Y,
+            snap_distance.0,
+            true,
+            filter,
+        ) {
+            let slope_angle = hit.normal1.angle_between(Vector::Y).abs();
+            if slope_angle <= max_slope_angle.0 {
+                position.y -= hit.time_of_impact;
+                linear_velocity.y = 0.0;
+                *grounded = Grounded::Ground(hit.normal1);
+                controller.was_grounded = true;
+            }
+        }
+    }
+}
+
 /// Slows down movement in the X direction.
-fn apply_movement_damping(mut query: Query<(&MovementDampingFactor, &mut LinearVelocity)>) {
-    for (damping_factor, mut linear_velocity) in &mut query {
+fn apply_movement_damping(
+    mut query: Query<(&MovementDampingFactor, &AirDamping, &Grounded, &mut LinearVelocity)>,
+) {
+    for (damping_factor, air_damping, grounded, mut linear_velocity) in &mut query {
+        let damping = if matches!(*grounded, Grounded::Ground(_)) {
+            damping_factor.0
+        } else {
+            air_damping.0
+        };
+
         // We could use `LinearDamping`, but we don't want to dampen movement along the Y axis
-        linear_velocity.x *= damping_factor.0;
+        linear_velocity.x *= damping;
     }
 }